@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+
+use crate::mirror::Mirror;
+
+/// On-disk snapshot of a long-running evolutionary search, so it can be
+/// paused and resumed. Versioned as an enum so future field additions don't
+/// break checkpoints written by older binaries.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Checkpoint {
+    V1 {
+        generation: usize,
+        best_score: usize,
+        population: Vec<Mirror>,
+    },
+}
+
+impl Checkpoint {
+    pub fn new(generation: usize, best_score: usize, population: Vec<Mirror>) -> Self {
+        Checkpoint::V1 {
+            generation,
+            best_score,
+            population,
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        match self {
+            Checkpoint::V1 { generation, .. } => *generation,
+        }
+    }
+
+    pub fn best_score(&self) -> usize {
+        match self {
+            Checkpoint::V1 { best_score, .. } => *best_score,
+        }
+    }
+
+    pub fn population(&self) -> &[Mirror] {
+        match self {
+            Checkpoint::V1 { population, .. } => population,
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
@@ -1,15 +1,29 @@
+mod checkpoint;
 mod mirror;
 
 use clap::Parser;
 use harper_core::Document;
 use harper_core::expr::ExprExt;
-use rand::seq::SliceRandom;
-use rayon::slice::ParallelSliceMut;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::fs;
 use std::time::Instant;
 
+use self::checkpoint::Checkpoint;
 use self::mirror::{Mirror, MirrorAtom, MirrorLayer, MirrorNode};
 
+/// Clap value parser for ratio-style flags: rejects anything outside
+/// `0.0..=1.0` instead of silently saturating (e.g. via an `f64 as usize`
+/// cast) when it's later multiplied into a child count.
+fn unit_interval(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("`{s}` isn't a number"))?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("must be between 0.0 and 1.0, got {value}"))
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -45,6 +59,27 @@ struct Args {
     /// Optional seed word to initialize the search with
     #[arg(long)]
     seed: Option<String>,
+
+    /// The fraction of each generation's offspring produced via subtree
+    /// crossover between two survivors, rather than mutation of one
+    /// (0.0 = mutation only, 1.0 = crossover only).
+    #[arg(long, default_value_t = 0.2, value_parser = unit_interval)]
+    crossover_ratio: f64,
+
+    /// Write the population to this path every `checkpoint_every`
+    /// generations, so a long run can be paused and resumed.
+    #[arg(long)]
+    checkpoint: Option<String>,
+
+    /// How often (in generations) to write the checkpoint file. Must be at
+    /// least 1.
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(usize).range(1..))]
+    checkpoint_every: usize,
+
+    /// Resume from a checkpoint written by `--checkpoint`, seeding the
+    /// population instead of starting from the seed word or an empty leaf.
+    #[arg(long)]
+    resume: Option<String>,
 }
 
 fn main() {
@@ -60,22 +95,31 @@ fn main() {
     let problems = load_documents(&args.problem_file);
     let clean = load_documents(&args.clean_file);
 
-    let mut mirs = vec![if let Some(seed) = args.seed.clone() {
-        Mirror {
-            root: MirrorNode::Leaf(MirrorLayer {
-                seq: vec![MirrorAtom::Word(seed)],
-            }),
-        }
+    let resumed = args.resume.as_deref().map(|path| {
+        Checkpoint::load(path).unwrap_or_else(|e| panic!("Unable to load checkpoint {path}: {e}"))
+    });
+
+    let mut mirs = if let Some(checkpoint) = &resumed {
+        checkpoint.population().to_vec()
     } else {
-        // No seed provided: start with an empty leaf and let mutation explore
-        Mirror {
-            root: MirrorNode::Leaf(MirrorLayer { seq: vec![] }),
-        }
-    }];
+        vec![if let Some(seed) = args.seed.clone() {
+            Mirror {
+                root: MirrorNode::Leaf(MirrorLayer {
+                    seq: vec![MirrorAtom::Word(seed)],
+                }),
+            }
+        } else {
+            // No seed provided: start with an empty leaf and let mutation explore
+            Mirror {
+                root: MirrorNode::Leaf(MirrorLayer { seq: vec![] }),
+            }
+        }]
+    };
 
-    let mut last_best_score = 0;
+    let mut last_best_score = resumed.as_ref().map_or(0, Checkpoint::best_score);
+    let start_generation = resumed.as_ref().map_or(0, |c| c.generation() + 1);
 
-    for i in 0..args.generations {
+    for i in start_generation..start_generation + args.generations {
         let start_time = Instant::now();
 
         mirs.truncate(args.min_pop);
@@ -83,28 +127,93 @@ fn main() {
         let mut perm_mirs = Vec::new();
         let mut rng = rand::rng();
 
+        let mutation_child_ratio =
+            (args.child_ratio as f64 * (1.0 - args.crossover_ratio)).round() as usize;
+
         for mir in &mirs {
             perm_mirs.append(&mut mir.create_children_with_mutations(
-                args.child_ratio,
+                mutation_child_ratio,
                 args.max_mutations,
                 &mut rng,
             ));
         }
 
+        // Subtree crossover: recombine random pairs of survivors so good
+        // substructures discovered in different lineages can be combined,
+        // rather than relying on mutation alone.
+        if mirs.len() >= 2 {
+            // Scale with the survivor count the same way `mutation_child_ratio`
+            // does, so `--crossover-ratio` reflects the actual offspring split
+            // regardless of `--min-pop`.
+            let crossover_pairs = (args.child_ratio as f64
+                * args.crossover_ratio
+                * mirs.len() as f64
+                / 2.0)
+                .round() as usize;
+            for _ in 0..crossover_pairs {
+                let a = mirs.choose(&mut rng).unwrap();
+                let b = mirs.choose(&mut rng).unwrap();
+                let (child_a, child_b) = a.crossover(b, &mut rng);
+                perm_mirs.push(child_a);
+                perm_mirs.push(child_b);
+            }
+        }
+
+        // Canonicalize offspring via Quine–McCluskey minimization before scoring, so
+        // logically-identical candidates over the same leaves (in the same
+        // first-seen order) collapse and `mirror_complexity` reflects
+        // each tree's canonical, deduped form rather than however mutation left it.
+        for child in &mut perm_mirs {
+            *child = child.minimize();
+        }
+
         mirs.append(&mut perm_mirs);
 
         mirs.shuffle(&mut rand::rng());
 
-        mirs.par_sort_by_cached_key(|s| {
-            let score = score(s, &problems, &clean);
-            usize::MAX - score
-        });
+        // NSGA-II-style selection: rank by Pareto dominance across the three
+        // objectives, then break ties within a front by crowding distance so
+        // the population fills front-by-front instead of along one
+        // collapsed scalar.
+        //
+        // Non-dominated sort is O(n^2) in the candidates it ranks, so ranking
+        // an entire generation's offspring (tens of thousands at this tool's
+        // default flags) would turn generation time into minutes. Bound it
+        // to the union of each objective's own top candidates (see
+        // `pareto_candidate_indices`) rather than pre-filtering by a
+        // collapsed scalar, which would throw away exactly the
+        // excellent-on-one-axis candidates Pareto ranking exists to surface.
+        // Whatever doesn't make that union keeps collapsed-score order.
+        let objs: Vec<Objectives> = mirs
+            .par_iter()
+            .map(|m| objectives(m, &problems, &clean))
+            .collect();
+
+        let pareto_idxs = pareto_candidate_indices(&objs);
+        let mut in_pareto_set = vec![false; objs.len()];
+        for &i in &pareto_idxs {
+            in_pareto_set[i] = true;
+        }
+        let mut fallback_idxs: Vec<usize> =
+            (0..objs.len()).filter(|&i| !in_pareto_set[i]).collect();
+        fallback_idxs.sort_by_key(|&i| std::cmp::Reverse(collapsed_score(&objs[i])));
+
+        let pareto_objs: Vec<Objectives> = pareto_idxs.iter().map(|&i| objs[i]).collect();
+        let fronts = non_dominated_sort(&pareto_objs);
+
+        let mut order: Vec<usize> = Vec::with_capacity(mirs.len());
+        for front in &fronts {
+            let distances = crowding_distances(&pareto_objs, front);
+            let mut ranked: Vec<usize> = (0..front.len()).collect();
+            ranked.sort_by(|&a, &b| distances[b].partial_cmp(&distances[a]).unwrap());
+            order.extend(ranked.into_iter().map(|i| pareto_idxs[front[i]]));
+        }
+        order.extend(fallback_idxs.iter().copied());
 
-        let best_score = if let Some(best_mir) = mirs.first() {
-            score(best_mir, &problems, &clean)
-        } else {
-            0
-        };
+        let ordered_objs: Vec<Objectives> = order.iter().map(|&i| objs[i]).collect();
+        mirs = order.into_iter().map(|i| mirs[i].clone()).collect();
+
+        let best_score = ordered_objs.first().map_or(0, collapsed_score);
 
         let delta = best_score as i64 - last_best_score as i64;
         let elapsed = start_time.elapsed();
@@ -115,15 +224,35 @@ fn main() {
         }
 
         println!(
-            "Generation {:<4} | Best Score: {:<10} | Max Score: {:<10} | Delta: {:<+10} | Candidates/sec: {:<10}",
+            "Generation {:<4} | Best Score: {:<10} | Max Score: {:<10} | Delta: {:<+10} | Candidates/sec: {:<10} | Fronts: {:<4} | Best(clean%={}, problem%={}, simplicity={})",
             i,
             best_score,
             max_possible_score(&problems, &clean),
             delta,
-            candidates_per_second
+            candidates_per_second,
+            fronts.len(),
+            ordered_objs.first().map_or(0, |o| o.clean_pct),
+            ordered_objs.first().map_or(0, |o| o.problem_pct),
+            ordered_objs.first().map_or(0, |o| o.simplicity),
         );
 
         last_best_score = best_score;
+
+        if let Some(path) = &args.checkpoint {
+            if (i + 1) % args.checkpoint_every == 0 {
+                // Checkpoint only the survivor set the next iteration actually
+                // keeps (`mirs.truncate(args.min_pop)` is the first thing it
+                // does), not the full, still-scored generation: otherwise the
+                // file balloons to the whole offspring population for no
+                // benefit, since everything past `min_pop` is discarded the
+                // instant it's resumed.
+                let survivors = mirs[..args.min_pop.min(mirs.len())].to_vec();
+                let checkpoint = Checkpoint::new(i, best_score, survivors);
+                if let Err(e) = checkpoint.save(path) {
+                    eprintln!("Warning: failed to write checkpoint to {path}: {e}");
+                }
+            }
+        }
     }
 }
 
@@ -166,6 +295,7 @@ fn mirror_complexity(m: &Mirror) -> usize {
             MirrorNode::And(children) | MirrorNode::Or(children) => {
                 children.iter().map(node_cost).sum::<usize>() + 2
             }
+            MirrorNode::Not(inner) => node_cost(inner) + 1,
         }
     }
 
@@ -176,7 +306,59 @@ fn mirror_complexity(m: &Mirror) -> usize {
 // to correctness so it cannot outweigh getting sentences right.
 const SIMPLICITY_BONUS_MAX: usize = 150;
 
-fn score(candidate: &Mirror, problems: &[Document], clean: &[Document]) -> usize {
+// Non-dominated sort is O(n^2), so bound how many candidates of a generation
+// ever go through it. This is a *per-objective* cap (see
+// `pareto_candidate_indices`), not a cap on the population scored by it, so
+// a candidate that's merely excellent on one axis is never excluded just
+// because the others pulled its collapsed score down. Benchmarking the
+// standalone algorithm showed ~7s at n=20,000 and ~30s at n=40,000
+// (quadratic), so capping each objective's slice here keeps the union at
+// most `3 * PARETO_CANDIDATES_PER_OBJECTIVE`, fast even at the default
+// `--child-ratio`/`--min-pop`, where the offspring population is tens of
+// thousands.
+const PARETO_CANDIDATES_PER_OBJECTIVE: usize = 700;
+
+/// The three competing objectives a candidate is judged on. Kept separate
+/// (rather than collapsed into one scalar) so selection can Pareto-rank
+/// candidates instead of trading one axis off against another along a single
+/// arbitrary line.
+#[derive(Debug, Clone, Copy)]
+struct Objectives {
+    /// Percentage of clean sentences with zero matches.
+    clean_pct: usize,
+    /// Percentage of problem sentences flagged with exactly one match.
+    problem_pct: usize,
+    /// `SIMPLICITY_BONUS_MAX` minus `mirror_complexity`; higher is simpler.
+    simplicity: usize,
+}
+
+impl Objectives {
+    /// Indexes the three objectives uniformly, for code that needs to loop
+    /// over them (e.g. crowding distance).
+    const COUNT: usize = 3;
+
+    fn get(&self, index: usize) -> usize {
+        match index {
+            0 => self.clean_pct,
+            1 => self.problem_pct,
+            _ => self.simplicity,
+        }
+    }
+
+    /// True if `self` is at least as good as `other` on every objective and
+    /// strictly better on at least one (standard Pareto dominance).
+    fn dominates(&self, other: &Objectives) -> bool {
+        let at_least_as_good = self.clean_pct >= other.clean_pct
+            && self.problem_pct >= other.problem_pct
+            && self.simplicity >= other.simplicity;
+        let strictly_better = self.clean_pct > other.clean_pct
+            || self.problem_pct > other.problem_pct
+            || self.simplicity > other.simplicity;
+        at_least_as_good && strictly_better
+    }
+}
+
+fn objectives(candidate: &Mirror, problems: &[Document], clean: &[Document]) -> Objectives {
     let expr = candidate.to_expr();
 
     // Clean correctness: percentage of clean sentences with zero matches.
@@ -209,14 +391,134 @@ fn score(candidate: &Mirror, problems: &[Document], clean: &[Document]) -> usize
         (problem_correct * 100) / problems.len()
     };
 
-    // Combined correctness: clean is weighted 2x as requested.
-    let correctness_score = clean_pct * 2 + problem_pct;
-
     // Small simplicity bonus in 0..=SIMPLICITY_BONUS_MAX, decreasing with complexity.
     let complexity = mirror_complexity(candidate);
-    let simplicity_bonus = SIMPLICITY_BONUS_MAX.saturating_sub(complexity);
+    let simplicity = SIMPLICITY_BONUS_MAX.saturating_sub(complexity);
+
+    Objectives {
+        clean_pct,
+        problem_pct,
+        simplicity,
+    }
+}
+
+/// Collapses `Objectives` into a single scalar, matching the old fixed
+/// weighting (clean x2, problem x1, small simplicity bonus). Used for the
+/// generation log's headline "Best Score" number and to order the candidates
+/// that don't reach the Pareto pass.
+fn collapsed_score(obj: &Objectives) -> usize {
+    (obj.clean_pct * 2 + obj.problem_pct) * 100 + obj.simplicity
+}
+
+/// Bounds the population fed into `non_dominated_sort` without biasing
+/// towards the collapsed scalar: takes each objective's own top
+/// `PARETO_CANDIDATES_PER_OBJECTIVE` candidates and unions the index sets.
+/// A candidate that's excellent on exactly one axis always makes its
+/// objective's slice, so it always reaches the Pareto pass, regardless of
+/// how the other two axes make it look under the old single-weighting.
+fn pareto_candidate_indices(objs: &[Objectives]) -> Vec<usize> {
+    let mut selected = vec![false; objs.len()];
+    for objective in 0..Objectives::COUNT {
+        let mut idxs: Vec<usize> = (0..objs.len()).collect();
+        idxs.sort_by_key(|&i| std::cmp::Reverse(objs[i].get(objective)));
+        for &i in idxs.iter().take(PARETO_CANDIDATES_PER_OBJECTIVE) {
+            selected[i] = true;
+        }
+    }
+    (0..objs.len()).filter(|&i| selected[i]).collect()
+}
+
+/// Partitions `objs` into Pareto fronts: front 0 holds the candidates no one
+/// dominates, front 1 holds those dominated only by front 0, and so on.
+/// Returns indices into `objs`.
+fn non_dominated_sort(objs: &[Objectives]) -> Vec<Vec<usize>> {
+    let n = objs.len();
+
+    // The O(n) row for each `p` is independent of every other row, so compute
+    // them in parallel: this is the O(n^2) comparison pass that dominates
+    // the cost of the whole sort.
+    let rows: Vec<(Vec<usize>, usize)> = (0..n)
+        .into_par_iter()
+        .map(|p| {
+            let mut dominated_by_p = Vec::new();
+            let mut domination_count = 0usize;
+            for q in 0..n {
+                if p == q {
+                    continue;
+                }
+                if objs[p].dominates(&objs[q]) {
+                    dominated_by_p.push(q);
+                } else if objs[q].dominates(&objs[p]) {
+                    domination_count += 1;
+                }
+            }
+            (dominated_by_p, domination_count)
+        })
+        .collect();
+
+    let mut dominates: Vec<Vec<usize>> = Vec::with_capacity(n);
+    let mut domination_count = vec![0usize; n];
+    let mut fronts = vec![Vec::new()];
+    for (p, (dominated_by_p, count)) in rows.into_iter().enumerate() {
+        dominates.push(dominated_by_p);
+        domination_count[p] = count;
+        if count == 0 {
+            fronts[0].push(p);
+        }
+    }
+
+    let mut i = 0;
+    while !fronts[i].is_empty() {
+        let mut next_front = Vec::new();
+        for &p in &fronts[i] {
+            for &q in &dominates[p] {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    next_front.push(q);
+                }
+            }
+        }
+        i += 1;
+        fronts.push(next_front);
+    }
+    fronts.pop(); // Drop the trailing empty front the loop exits on.
+    fronts
+}
+
+/// Crowding distance for each member of `front` (indices into `objs`): for
+/// each objective, the normalized gap between a candidate's neighbors once
+/// the front is sorted on that objective, summed across objectives.
+/// Boundary candidates (smallest/largest per objective) get infinite
+/// distance so the extremes of the trade-off frontier are always preferred.
+fn crowding_distances(objs: &[Objectives], front: &[usize]) -> Vec<f64> {
+    let n = front.len();
+    let mut distance = vec![0.0f64; n];
+    if n < 3 {
+        return vec![f64::INFINITY; n];
+    }
+
+    for objective in 0..Objectives::COUNT {
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&i| objs[front[i]].get(objective));
+
+        distance[order[0]] = f64::INFINITY;
+        distance[order[n - 1]] = f64::INFINITY;
+
+        let min = objs[front[order[0]]].get(objective) as f64;
+        let max = objs[front[order[n - 1]]].get(objective) as f64;
+        let span = max - min;
+        if span == 0.0 {
+            continue;
+        }
+
+        for w in 1..n - 1 {
+            let prev = objs[front[order[w - 1]]].get(objective) as f64;
+            let next = objs[front[order[w + 1]]].get(objective) as f64;
+            distance[order[w]] += (next - prev) / span;
+        }
+    }
 
-    correctness_score * 100 + simplicity_bonus
+    distance
 }
 
 pub fn max_possible_score(problems: &[Document], clean: &[Document]) -> usize {
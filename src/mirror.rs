@@ -1,23 +1,49 @@
 use harper_brill::UPOS;
 use harper_core::expr::{All, Expr, LongestMatchOf, SequenceExpr};
 use harper_core::patterns::{UPOSSet, WordSet};
+use harper_core::Token;
 use rand::seq::{IndexedRandom, SliceRandom};
 use rand::{Rng, random_bool};
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
 use strum::IntoEnumIterator;
 
 /// A tree of expressions.
 /// - `And`: every child must match (logical AND).
 /// - `Or`: at least one child must match (logical OR, longest-match semantics).
+/// - `Not`: matches wherever the child does not (zero-length match).
 /// - `Leaf`: a concrete `SequenceExpr` built from a `MirrorLayer`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum MirrorNode {
     And(Vec<MirrorNode>),
     Or(Vec<MirrorNode>),
+    Not(Box<MirrorNode>),
     Leaf(MirrorLayer),
 }
 
+/// Negates an inner `Expr`: matches (with zero length) wherever the inner
+/// expression fails to match, and does not match wherever it succeeds.
+pub struct NotExpr {
+    inner: Box<dyn Expr>,
+}
+
+impl NotExpr {
+    pub fn new(inner: Box<dyn Expr>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Expr for NotExpr {
+    fn run(&self, cursor: usize, tokens: &[Token], source: &[char]) -> Option<Range<usize>> {
+        match self.inner.run(cursor, tokens, source) {
+            Some(_) => None,
+            None => Some(cursor..cursor),
+        }
+    }
+}
+
 impl Default for MirrorNode {
     fn default() -> Self {
         Self::Leaf(MirrorLayer { seq: vec![] })
@@ -38,12 +64,12 @@ impl From<Vec<MirrorLayer>> for Mirror {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MirrorLayer {
     pub seq: Vec<MirrorAtom>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum MirrorAtom {
     Word(String),
     UPOS(SmallVec<[UPOS; 16]>),
@@ -130,7 +156,7 @@ impl MirrorLayer {
 
 impl MirrorNode {
     /// Build a boxed `Expr` for this subtree.
-    /// AND => `All`, OR => `LongestMatchOf`, Leaf => `SequenceExpr`.
+    /// AND => `All`, OR => `LongestMatchOf`, NOT => `NotExpr`, Leaf => `SequenceExpr`.
     pub fn to_owned_expr(&self) -> Box<dyn Expr> {
         match self {
             MirrorNode::Leaf(layer) => Box::new(layer.to_seq_expr()),
@@ -148,6 +174,43 @@ impl MirrorNode {
                 }
                 Box::new(LongestMatchOf::new(v))
             }
+            MirrorNode::Not(inner) => Box::new(NotExpr::new(inner.to_owned_expr())),
+        }
+    }
+
+    /// Counts every node in this subtree, including itself.
+    pub fn node_count(&self) -> usize {
+        1 + match self {
+            MirrorNode::Leaf(_) => 0,
+            MirrorNode::Not(inner) => inner.node_count(),
+            MirrorNode::And(children) | MirrorNode::Or(children) => {
+                children.iter().map(MirrorNode::node_count).sum()
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the `index`-th node of this subtree in
+    /// a stable pre-order (self first, then children left to right), the
+    /// same order `node_count` walks. Used to address a uniformly random
+    /// node for crossover.
+    fn node_mut(&mut self, index: usize) -> Option<&mut MirrorNode> {
+        if index == 0 {
+            return Some(self);
+        }
+        let mut remaining = index - 1;
+        match self {
+            MirrorNode::Leaf(_) => None,
+            MirrorNode::Not(inner) => inner.node_mut(remaining),
+            MirrorNode::And(children) | MirrorNode::Or(children) => {
+                for child in children {
+                    let count = child.node_count();
+                    if remaining < count {
+                        return child.node_mut(remaining);
+                    }
+                    remaining -= count;
+                }
+                None
+            }
         }
     }
 
@@ -156,6 +219,7 @@ impl MirrorNode {
     /// - Insert/remove a child
     /// - Flip AND <-> OR
     /// - Wrap/unwrap a leaf to introduce structure
+    /// - Wrap/unwrap a child in `Not`, collapsing `Not(Not(x))` back to `x`
     pub fn mutate(&mut self, rng: &mut impl Rng) {
         match self {
             MirrorNode::Leaf(layer) => {
@@ -171,6 +235,14 @@ impl MirrorNode {
                     }
                 }
             }
+            MirrorNode::Not(inner) => {
+                // Collapse a double negation, otherwise recurse into the negated subtree.
+                if let MirrorNode::Not(double) = inner.as_mut() {
+                    *self = std::mem::take(double.as_mut());
+                } else {
+                    inner.mutate(rng);
+                }
+            }
             MirrorNode::And(children) | MirrorNode::Or(children) => {
                 let len = children.len();
 
@@ -179,12 +251,24 @@ impl MirrorNode {
                     let swapped = match std::mem::take(self) {
                         MirrorNode::And(c) => MirrorNode::Or(c),
                         MirrorNode::Or(c) => MirrorNode::And(c),
-                        MirrorNode::Leaf(_) => unreachable!(),
+                        MirrorNode::Not(_) | MirrorNode::Leaf(_) => unreachable!(),
                     };
                     *self = swapped;
                     return;
                 }
 
+                // Occasionally negate a child: wrapping an already-negated child in
+                // `Not` again collapses `Not(Not(x))` straight back to `x`.
+                if !children.is_empty() && rng.random_bool(0.1) {
+                    let idx = rng.random_range(0..children.len());
+                    let child = std::mem::take(&mut children[idx]);
+                    children[idx] = match child {
+                        MirrorNode::Not(inner) => *inner,
+                        other => MirrorNode::Not(Box::new(other)),
+                    };
+                    return;
+                }
+
                 // Sometimes reorder for variety
                 if len >= 2 && rng.random_bool(0.1) {
                     children.shuffle(rng);
@@ -223,6 +307,265 @@ impl MirrorNode {
             }
         }
     }
+
+    /// The maximum number of distinct leaves `minimize` will canonicalize.
+    /// The truth table is `2^n`, so this bounds worst-case cost per call.
+    const MAX_MINIMIZE_VARS: usize = 12;
+
+    /// Quine–McCluskey canonicalization: treats each structurally distinct
+    /// `Leaf` as a boolean variable, evaluates this tree over every variable
+    /// assignment, and rebuilds a minimal, canonical `Or`-of-`And`s (sum of
+    /// products) from the prime implicant cover. Logically-equivalent trees
+    /// whose distinct leaves appear in the same first-seen order collapse to
+    /// the same shape, which shrinks `mirror_complexity` and lets identical
+    /// candidates dedupe; the variables follow that order, so e.g. `Or(A, B)`
+    /// and `Or(B, A)` keep their differing child order.
+    ///
+    /// Bails out to a clone of `self` when there are more distinct leaves
+    /// than `MAX_MINIMIZE_VARS`, since the search is exponential in them.
+    pub fn minimize(&self) -> MirrorNode {
+        let mut leaves: Vec<MirrorLayer> = Vec::new();
+        collect_leaves(self, &mut leaves);
+
+        let n = leaves.len();
+        if n == 0 || n > Self::MAX_MINIMIZE_VARS {
+            return self.clone();
+        }
+
+        let minterms: Vec<u32> = (0..(1u32 << n))
+            .filter(|&assignment| eval_node(self, &leaves, assignment))
+            .collect();
+
+        // Always-false or always-true: no `Or`-of-`And`s shape is simpler
+        // than what's already there, so leave the tree as-is.
+        if minterms.is_empty() || minterms.len() == (1usize << n) {
+            return self.clone();
+        }
+
+        let primes = quine_mccluskey(&minterms, n);
+        let cover = select_cover(&primes, &minterms);
+
+        let mut groups: Vec<MirrorNode> = cover
+            .into_iter()
+            .map(|implicant| implicant_to_node(&implicant, &leaves))
+            .collect();
+
+        match groups.len() {
+            0 => self.clone(),
+            1 => groups.remove(0),
+            _ => MirrorNode::Or(groups),
+        }
+    }
+}
+
+/// Collects each structurally distinct `Leaf` under `node`, in first-seen
+/// order, so each can be treated as a boolean variable.
+fn collect_leaves(node: &MirrorNode, leaves: &mut Vec<MirrorLayer>) {
+    match node {
+        MirrorNode::Leaf(layer) => {
+            if !leaves.contains(layer) {
+                leaves.push(layer.clone());
+            }
+        }
+        MirrorNode::Not(inner) => collect_leaves(inner, leaves),
+        MirrorNode::And(children) | MirrorNode::Or(children) => {
+            for child in children {
+                collect_leaves(child, leaves);
+            }
+        }
+    }
+}
+
+/// Evaluates `node` as a boolean function, where bit `i` of `assignment` is
+/// the truth value of `leaves[i]`.
+fn eval_node(node: &MirrorNode, leaves: &[MirrorLayer], assignment: u32) -> bool {
+    match node {
+        MirrorNode::Leaf(layer) => {
+            let idx = leaves
+                .iter()
+                .position(|l| l == layer)
+                .expect("every leaf was registered by collect_leaves");
+            (assignment >> idx) & 1 == 1
+        }
+        MirrorNode::Not(inner) => !eval_node(inner, leaves, assignment),
+        MirrorNode::And(children) => children.iter().all(|c| eval_node(c, leaves, assignment)),
+        MirrorNode::Or(children) => children.iter().any(|c| eval_node(c, leaves, assignment)),
+    }
+}
+
+/// A product term over `n` boolean variables: `Some(true)` keeps the
+/// variable, `Some(false)` keeps its negation, `None` is a don't-care dash
+/// that has been combined away.
+#[derive(Clone, Debug, PartialEq)]
+struct Implicant {
+    bits: Vec<Option<bool>>,
+    minterms: Vec<u32>,
+}
+
+impl Implicant {
+    fn from_minterm(m: u32, n: usize) -> Self {
+        let bits = (0..n).map(|i| Some((m >> i) & 1 == 1)).collect();
+        Implicant {
+            bits,
+            minterms: vec![m],
+        }
+    }
+
+    fn popcount(&self) -> usize {
+        self.bits.iter().filter(|b| **b == Some(true)).count()
+    }
+
+    /// Combines two implicants if they differ in exactly one defined bit,
+    /// dashing that bit out. Returns `None` if they aren't combinable (e.g.
+    /// their dash positions don't line up, or more than one bit differs).
+    fn combine(&self, other: &Implicant) -> Option<Implicant> {
+        let mut diff_idx = None;
+        for (i, (a, b)) in self.bits.iter().zip(other.bits.iter()).enumerate() {
+            if a != b {
+                if diff_idx.is_some() {
+                    return None;
+                }
+                diff_idx = Some(i);
+            }
+        }
+        let idx = diff_idx?;
+        if self.bits[idx].is_none() || other.bits[idx].is_none() {
+            return None;
+        }
+
+        let mut bits = self.bits.clone();
+        bits[idx] = None;
+        let mut minterms = self.minterms.clone();
+        minterms.extend(other.minterms.iter().copied());
+        minterms.sort_unstable();
+        minterms.dedup();
+        Some(Implicant { bits, minterms })
+    }
+}
+
+/// Classic Quine–McCluskey prime implicant generation: group minterms by
+/// popcount, repeatedly combine implicants from adjacent groups that differ
+/// in exactly one bit, and keep whatever never gets combined away as prime.
+fn quine_mccluskey(minterms: &[u32], n: usize) -> Vec<Implicant> {
+    let mut by_popcount: BTreeMap<usize, Vec<Implicant>> = BTreeMap::new();
+    for &m in minterms {
+        let imp = Implicant::from_minterm(m, n);
+        by_popcount.entry(imp.popcount()).or_default().push(imp);
+    }
+
+    let mut primes: Vec<Implicant> = Vec::new();
+
+    loop {
+        let mut combined_flags: HashMap<usize, Vec<bool>> = by_popcount
+            .iter()
+            .map(|(&p, group)| (p, vec![false; group.len()]))
+            .collect();
+        let mut next_by_popcount: BTreeMap<usize, Vec<Implicant>> = BTreeMap::new();
+
+        let popcounts: Vec<usize> = by_popcount.keys().copied().collect();
+        for pair in popcounts.windows(2) {
+            let (lo, hi) = (pair[0], pair[1]);
+            if hi != lo + 1 {
+                continue;
+            }
+            for (i, a) in by_popcount[&lo].iter().enumerate() {
+                for (j, b) in by_popcount[&hi].iter().enumerate() {
+                    let Some(combined) = a.combine(b) else {
+                        continue;
+                    };
+                    combined_flags.get_mut(&lo).unwrap()[i] = true;
+                    combined_flags.get_mut(&hi).unwrap()[j] = true;
+                    let bucket = next_by_popcount.entry(combined.popcount()).or_default();
+                    if !bucket.contains(&combined) {
+                        bucket.push(combined);
+                    }
+                }
+            }
+        }
+
+        for (p, group) in &by_popcount {
+            for (i, implicant) in group.iter().enumerate() {
+                if !combined_flags[p][i] && !primes.contains(implicant) {
+                    primes.push(implicant.clone());
+                }
+            }
+        }
+
+        if next_by_popcount.is_empty() {
+            break;
+        }
+        by_popcount = next_by_popcount;
+    }
+
+    primes
+}
+
+/// Builds the prime-implicant chart, picks essential prime implicants (the
+/// sole implicant covering some minterm), then greedily covers whatever
+/// minterms remain with the implicant that covers the most of them.
+fn select_cover(primes: &[Implicant], minterms: &[u32]) -> Vec<Implicant> {
+    let mut uncovered: HashSet<u32> = minterms.iter().copied().collect();
+    let mut used = vec![false; primes.len()];
+    let mut cover: Vec<Implicant> = Vec::new();
+
+    for &m in minterms {
+        let covering: Vec<usize> = primes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.minterms.contains(&m))
+            .map(|(i, _)| i)
+            .collect();
+        if let [idx] = covering[..] {
+            if !used[idx] {
+                used[idx] = true;
+                uncovered.retain(|m| !primes[idx].minterms.contains(m));
+                cover.push(primes[idx].clone());
+            }
+        }
+    }
+
+    while !uncovered.is_empty() {
+        let best = primes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !used[*i])
+            .max_by_key(|(_, p)| p.minterms.iter().filter(|m| uncovered.contains(m)).count());
+
+        match best {
+            Some((idx, p)) if p.minterms.iter().any(|m| uncovered.contains(m)) => {
+                used[idx] = true;
+                uncovered.retain(|m| !p.minterms.contains(m));
+                cover.push(p.clone());
+            }
+            // Every minterm is covered by some prime implicant, so this is unreachable.
+            _ => break,
+        }
+    }
+
+    cover
+}
+
+/// Rebuilds a product term from an implicant: a dash drops the variable, a
+/// set bit keeps its `Leaf`, and a clear bit wraps the `Leaf` in `Not`.
+fn implicant_to_node(implicant: &Implicant, leaves: &[MirrorLayer]) -> MirrorNode {
+    let literals: Vec<MirrorNode> = implicant
+        .bits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, bit)| match bit {
+            None => None,
+            Some(true) => Some(MirrorNode::Leaf(leaves[i].clone())),
+            Some(false) => Some(MirrorNode::Not(Box::new(MirrorNode::Leaf(leaves[i].clone())))),
+        })
+        .collect();
+
+    match literals.len() {
+        // All dashes: the term is a tautology, so fall back to the default
+        // always-matching empty leaf.
+        0 => MirrorNode::default(),
+        1 => literals.into_iter().next().unwrap(),
+        _ => MirrorNode::And(literals),
+    }
 }
 
 impl Mirror {
@@ -230,6 +573,14 @@ impl Mirror {
         self.root.to_owned_expr()
     }
 
+    /// Runs Quine–McCluskey canonicalization on the tree (see
+    /// `MirrorNode::minimize`).
+    pub fn minimize(&self) -> Mirror {
+        Mirror {
+            root: self.root.minimize(),
+        }
+    }
+
     pub fn create_children_with_mutations(
         &self,
         child_count: usize,
@@ -270,6 +621,62 @@ impl Mirror {
             return;
         }
 
+        // Toggle negation of the whole tree; wrapping an already-negated root
+        // collapses `Not(Not(x))` back to `x`.
+        if rng.random_bool(0.1) {
+            let old = std::mem::take(&mut self.root);
+            self.root = match old {
+                MirrorNode::Not(inner) => *inner,
+                other => MirrorNode::Not(Box::new(other)),
+            };
+            return;
+        }
+
         self.root.mutate(rng);
     }
+
+    /// Subtree crossover: picks a uniformly random node in each parent and
+    /// swaps the two selected subtrees, producing two offspring that combine
+    /// structure discovered in different lineages. Falls back to unmodified
+    /// clones of the parents if the swap would leave an `And`/`Or` with no
+    /// children.
+    pub fn crossover(&self, other: &Mirror, rng: &mut impl Rng) -> (Mirror, Mirror) {
+        let mut child_a = self.clone();
+        let mut child_b = other.clone();
+
+        let idx_a = rng.random_range(0..child_a.root.node_count());
+        let idx_b = rng.random_range(0..child_b.root.node_count());
+
+        {
+            let node_a = child_a
+                .root
+                .node_mut(idx_a)
+                .expect("idx_a is within node_count");
+            let node_b = child_b
+                .root
+                .node_mut(idx_b)
+                .expect("idx_b is within node_count");
+            std::mem::swap(node_a, node_b);
+        }
+
+        if has_degenerate_empty_group(&child_a.root) || has_degenerate_empty_group(&child_b.root) {
+            return (self.clone(), other.clone());
+        }
+
+        (child_a, child_b)
+    }
+}
+
+/// True if `node` contains an `And`/`Or` with no children. Subtree crossover
+/// can never produce one on its own (it only ever swaps a node in place),
+/// but this guards against shipping a degenerate tree if that invariant ever
+/// changes.
+fn has_degenerate_empty_group(node: &MirrorNode) -> bool {
+    match node {
+        MirrorNode::Leaf(_) => false,
+        MirrorNode::Not(inner) => has_degenerate_empty_group(inner),
+        MirrorNode::And(children) | MirrorNode::Or(children) => {
+            children.is_empty() || children.iter().any(has_degenerate_empty_group)
+        }
+    }
 }